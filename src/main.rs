@@ -12,6 +12,15 @@ use std::time::{Duration, Instant};
 enum GenerationMode {
     Grayscale,
     Colorful,
+    /// Coordinate-hashed, resolution-independent grayscale noise. Each pixel is
+    /// derived from its `(x, y)` via [`pixel_hash`] and rendered through
+    /// [`pixel_grayscale`].
+    HashedGrayscale,
+    /// Coordinate-hashed, resolution-independent colorful noise. Each pixel is
+    /// derived from its `(x, y)` via [`pixel_hash`] and rendered through
+    /// [`pixel_colorful`].
+    HashedColorful,
+    Palette,
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -45,10 +54,13 @@ impl XorShift32 {
 fn main() -> io::Result<()> {
     let genmode = ask_enum(
         "Enter mode",
-        "[ERR] Invalid mode\nValid modes are:\n\t- grayscale\n\t- colorful",
+        "[ERR] Invalid mode\nValid modes are:\n\t- grayscale\n\t- colorful\n\t- hashed-grayscale\n\t- hashed-colorful\n\t- palette",
         &[
             ("grayscale", GenerationMode::Grayscale),
             ("colorful", GenerationMode::Colorful),
+            ("hashed-grayscale", GenerationMode::HashedGrayscale),
+            ("hashed-colorful", GenerationMode::HashedColorful),
+            ("palette", GenerationMode::Palette),
         ],
         io::stdout().lock(),
     )?;
@@ -70,10 +82,37 @@ fn main() -> io::Result<()> {
         io::stdout().lock(),
     )?;
 
-    let output_file = PathBuf::from(path).with_extension("png");
+    let format = ask_enum(
+        "Enter output format",
+        "[ERR] Invalid format\nValid formats are:\n\t- png\n\t- bmp\n\t- tiff\n\t- qoi\n\t- jpeg",
+        &[
+            ("png", ImageFormat::Png),
+            ("bmp", ImageFormat::Bmp),
+            ("tiff", ImageFormat::Tiff),
+            ("qoi", ImageFormat::Qoi),
+            ("jpeg", ImageFormat::Jpeg),
+        ],
+        io::stdout().lock(),
+    )?;
+
+    let extension = format.extensions_str().first().copied().unwrap_or("png");
+    let output_file = PathBuf::from(path).with_extension(extension);
+
+    let palette = match genmode {
+        GenerationMode::Palette => {
+            let palette_path: String = ask(
+                "Enter palette path (empty for default)",
+                "[ERR] Invalid palette path",
+                io::stdout().lock(),
+            )?;
+            load_palette(palette_path.trim())?
+        }
+        _ => default_palette(),
+    };
 
     let (result, total_time) = time(|| -> io::Result<()> {
-        let (rows, generation_time) = time(|| generate_random_pixels(seed, width, height, genmode));
+        let (rows, generation_time) =
+            time(|| generate_random_pixels(seed, width, height, genmode, &palette));
         writeln!(
             io::stderr(),
             "Generation finished in {}",
@@ -90,7 +129,7 @@ fn main() -> io::Result<()> {
             format_duration(conversion_time)
         )?;
 
-        let (write_result, write_time) = time(|| write_image_to_file(&output_file, &img));
+        let (write_result, write_time) = time(|| write_image_to_file(&output_file, &img, format));
         write_result?;
         writeln!(
             io::stderr(),
@@ -107,17 +146,127 @@ fn main() -> io::Result<()> {
     Ok(())
 }
 
-fn pixel_grayscale(num: u32) -> Rgb<u8> {
-    let clamped = num % 256;
-    Rgb([clamped as u8, clamped as u8, clamped as u8])
+fn pixel_grayscale(num: u32, slot: &mut [u8; 3]) {
+    let clamped = (num % 256) as u8;
+    *slot = [clamped, clamped, clamped];
 }
 
-fn pixel_colorful(num: u32) -> Rgb<u8> {
+fn pixel_colorful(num: u32, slot: &mut [u8; 3]) {
     let r = (num << 24i32) >> 24i32;
     let g = (num << 16i32) >> 24i32;
     let b = (num << 8i32) >> 24i32;
 
-    Rgb([r as u8, g as u8, b as u8])
+    *slot = [r as u8, g as u8, b as u8];
+}
+
+/// Derive a pixel value purely from its `(x, y)` coordinates and the seed,
+/// so every pixel is independently reproducible regardless of scan order or
+/// image size. Uses an xxHash32-style avalanche over the two coordinates,
+/// which makes the output tileable and trivially parallel with no RNG state.
+fn pixel_hash(seed: u32, x: u32, y: u32) -> u32 {
+    const PRIME32_2: u32 = 0x85EBCA77;
+    const PRIME32_3: u32 = 0xC2B2AE3D;
+    const PRIME32_4: u32 = 0x27D4EB2F;
+    const PRIME32_5: u32 = 0x165667B1;
+
+    let mut acc = seed.wrapping_add(PRIME32_5);
+
+    for coord in [x, y] {
+        acc = acc.wrapping_add(coord.wrapping_mul(PRIME32_3));
+        acc = acc.rotate_left(17).wrapping_mul(PRIME32_4);
+    }
+
+    acc ^= acc >> 15i32;
+    acc = acc.wrapping_mul(PRIME32_2);
+    acc ^= acc >> 13i32;
+    acc = acc.wrapping_mul(PRIME32_3);
+    acc ^= acc >> 16i32;
+
+    acc
+}
+
+/// The color table used by [`GenerationMode::Palette`] when the user does not
+/// supply one — a compact retro-flavoured ramp so the default output still
+/// looks deliberate rather than fully random.
+fn default_palette() -> Vec<Rgb<u8>> {
+    vec![
+        Rgb([0x1a, 0x1c, 0x2c]),
+        Rgb([0x5d, 0x27, 0x5d]),
+        Rgb([0xb1, 0x3e, 0x53]),
+        Rgb([0xef, 0x7d, 0x57]),
+        Rgb([0xff, 0xcd, 0x75]),
+        Rgb([0xa7, 0xf0, 0x70]),
+        Rgb([0x38, 0xb7, 0x64]),
+        Rgb([0x25, 0x71, 0x79]),
+    ]
+}
+
+/// Load a color table from `path`, returning [`default_palette`] when `path`
+/// is empty. The file holds one color per line, either as an `R,G,B` triple of
+/// decimal bytes or as a hex string (`#rrggbb` or `rrggbb`); blank lines are
+/// ignored. The resulting palette is validated to be non-empty.
+fn load_palette(path: &str) -> io::Result<Vec<Rgb<u8>>> {
+    if path.is_empty() {
+        return Ok(default_palette());
+    }
+
+    let contents = std::fs::read_to_string(path)?;
+    let mut palette = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        palette.push(parse_palette_color(line)?);
+    }
+
+    if palette.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Palette file did not contain any colors",
+        ));
+    }
+
+    Ok(palette)
+}
+
+fn parse_palette_color(line: &str) -> io::Result<Rgb<u8>> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid palette color: {}", line),
+        )
+    };
+
+    if line.contains(',') {
+        let mut channels = line.split(',');
+        let mut next = || -> io::Result<u8> {
+            channels
+                .next()
+                .and_then(|c| c.trim().parse().ok())
+                .ok_or_else(invalid)
+        };
+
+        let rgb = Rgb([next()?, next()?, next()?]);
+        if channels.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(rgb)
+    } else {
+        let hex = line.strip_prefix('#').unwrap_or(line);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+
+        let r = u8::from_str_radix(&hex[0..2], 16).map_err(|_| invalid())?;
+        let g = u8::from_str_radix(&hex[2..4], 16).map_err(|_| invalid())?;
+        let b = u8::from_str_radix(&hex[4..6], 16).map_err(|_| invalid())?;
+
+        Ok(Rgb([r, g, b]))
+    }
 }
 
 // fn random_pixel(rng: &mut XorShift32, mode: GenerationMode) -> Rgb<u8> {
@@ -241,7 +390,8 @@ fn generate_random_pixels(
     width: u32,
     height: u32,
     genmode: GenerationMode,
-) -> Vec<Rgb<u8>> {
+    palette: &[Rgb<u8>],
+) -> Vec<u8> {
     // // older implementation that worked
     // let mut master_rng =
     //     XorShift32::new(seed.wrapping_mul(0xDEADBEEF).wrapping_add(0xCAFEBABE)).step_forward(100);
@@ -270,41 +420,76 @@ fn generate_random_pixels(
 
     // -------------------------------------------------------------------------------
 
-    // old working example 2
-    let mut master_rng =
-        XorShift32::new(seed.wrapping_mul(0xDEADBEEF).wrapping_add(0xCAFEBABE)).step_forward(100);
-
-    let rngs = (0..height)
-        .map(|_| {
-            XorShift32::new(
-                master_rng
-                    .next()
-                    .wrapping_mul(0x4d0df4c7)
-                    .wrapping_add(0x8980ab2b),
-            )
-            .step_forward(100)
-        })
-        .collect::<Vec<_>>();
-
-    let mut rows = vec![Rgb([0, 0, 0]); (width * height) as usize];
+    // Per-row RNGs, seeded off a master RNG. Only the stateful modes need
+    // these; `HashedGrayscale` and `HashedColorful` derive every pixel from its
+    // coordinates, so they skip the master-RNG setup pass entirely.
+    let make_rngs = || {
+        let mut master_rng =
+            XorShift32::new(seed.wrapping_mul(0xDEADBEEF).wrapping_add(0xCAFEBABE)).step_forward(100);
+
+        (0..height)
+            .map(|_| {
+                XorShift32::new(
+                    master_rng
+                        .next()
+                        .wrapping_mul(0x4d0df4c7)
+                        .wrapping_add(0x8980ab2b),
+                )
+                .step_forward(100)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let row_bytes = width as usize * 3;
+    let mut rows = vec![0u8; width as usize * height as usize * 3];
     match genmode {
         GenerationMode::Grayscale => {
-            rows.par_chunks_exact_mut(width as usize)
-                .zip(rngs)
+            rows.par_chunks_exact_mut(row_bytes)
+                .zip(make_rngs())
                 .for_each(|(row, mut rng)| {
-                    for pixel in row {
-                        let num = rng.next();
-                        *pixel = pixel_grayscale(num);
+                    for slot in row.chunks_exact_mut(3) {
+                        let slot: &mut [u8; 3] = slot.try_into().unwrap();
+                        pixel_grayscale(rng.next(), slot);
                     }
                 });
         }
         GenerationMode::Colorful => {
-            rows.par_chunks_exact_mut(width as usize)
-                .zip(rngs)
+            rows.par_chunks_exact_mut(row_bytes)
+                .zip(make_rngs())
+                .for_each(|(row, mut rng)| {
+                    for slot in row.chunks_exact_mut(3) {
+                        let slot: &mut [u8; 3] = slot.try_into().unwrap();
+                        pixel_colorful(rng.next(), slot);
+                    }
+                });
+        }
+        GenerationMode::HashedGrayscale => {
+            rows.par_chunks_exact_mut(row_bytes)
+                .enumerate()
+                .for_each(|(y, row)| {
+                    for (x, slot) in row.chunks_exact_mut(3).enumerate() {
+                        let slot: &mut [u8; 3] = slot.try_into().unwrap();
+                        pixel_grayscale(pixel_hash(seed, x as u32, y as u32), slot);
+                    }
+                });
+        }
+        GenerationMode::HashedColorful => {
+            rows.par_chunks_exact_mut(row_bytes)
+                .enumerate()
+                .for_each(|(y, row)| {
+                    for (x, slot) in row.chunks_exact_mut(3).enumerate() {
+                        let slot: &mut [u8; 3] = slot.try_into().unwrap();
+                        pixel_colorful(pixel_hash(seed, x as u32, y as u32), slot);
+                    }
+                });
+        }
+        GenerationMode::Palette => {
+            rows.par_chunks_exact_mut(row_bytes)
+                .zip(make_rngs())
                 .for_each(|(row, mut rng)| {
-                    for pixel in row {
+                    for slot in row.chunks_exact_mut(3) {
                         let num = rng.next();
-                        *pixel = pixel_colorful(num);
+                        slot.copy_from_slice(&palette[num as usize % palette.len()].0);
                     }
                 });
         }
@@ -344,15 +529,10 @@ fn generate_random_pixels(
 }
 
 fn convert_pixels_to_image_buffer(
-    rows: Vec<Rgb<u8>>,
+    raw_pixels: Vec<u8>,
     width: u32,
     height: u32,
 ) -> io::Result<ImageBuffer<Rgb<u8>, Vec<u8>>> {
-    let raw_pixels = rows
-        .into_iter()
-        .flat_map(|pixel| pixel.0)
-        .collect::<Vec<u8>>();
-
     ImageBuffer::from_raw(width, height, raw_pixels).ok_or_else(|| {
         io::Error::new(
             io::ErrorKind::InvalidData,
@@ -364,6 +544,7 @@ fn convert_pixels_to_image_buffer(
 fn write_image_to_file(
     output_file: &PathBuf,
     img: &ImageBuffer<Rgb<u8>, Vec<u8>>,
+    format: ImageFormat,
 ) -> io::Result<()> {
     let file = OpenOptions::new()
         .create(true)
@@ -372,9 +553,7 @@ fn write_image_to_file(
         .open(output_file)?;
     let mut bw = BufWriter::new(file);
 
-    if let Err(e) = img.write_to(&mut bw, ImageFormat::Png) {
-        writeln!(io::stderr(), "Error writing image: {}", e)?;
-    };
+    img.write_to(&mut bw, format).map_err(io::Error::other)?;
 
     Ok(())
 }